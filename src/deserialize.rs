@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::tokenize::Number;
+use crate::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeserializeError {
+    MissingField(String),
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "missing field `{field}`"),
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "expected {expected}, found {found}")
+            }
+        }
+    }
+}
+
+/// Converts a dynamic `Value` into a concrete Rust type.
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, DeserializeError>;
+}
+
+/// Convenience wrapper so callers can write `from_value::<T>(&value)`
+/// instead of `T::from_value(&value)`.
+pub fn from_value<T: FromValue>(value: &Value) -> Result<T, DeserializeError> {
+    T::from_value(value)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Boolean(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_mismatch(expected: &'static str, found: &Value) -> DeserializeError {
+    DeserializeError::TypeMismatch {
+        expected,
+        found: type_name(found),
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self, DeserializeError> {
+        match value {
+            Value::Boolean(b) => Ok(*b),
+            other => Err(type_mismatch("boolean", other)),
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<Self, DeserializeError> {
+        match value {
+            Value::Number(Number::Integer(i)) => Ok(*i as f64),
+            Value::Number(Number::Float(f)) => Ok(*f),
+            other => Err(type_mismatch("number", other)),
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, DeserializeError> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            other => Err(type_mismatch("string", other)),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, DeserializeError> {
+        match value {
+            Value::Null => Ok(None),
+            other => T::from_value(other).map(Some),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Result<Self, DeserializeError> {
+        match value {
+            Value::Array(items) => items.iter().map(T::from_value).collect(),
+            other => Err(type_mismatch("array", other)),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for HashMap<String, T> {
+    fn from_value(value: &Value) -> Result<Self, DeserializeError> {
+        match value {
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, val)| T::from_value(val).map(|parsed| (key.clone(), parsed)))
+                .collect(),
+            other => Err(type_mismatch("object", other)),
+        }
+    }
+}
+
+/// Pulls a named field out of a `Value::Object`, returning `MissingField`
+/// if the key is absent or a `TypeMismatch` if `value` isn't an object.
+pub fn get_field<'a>(value: &'a Value, field: &str) -> Result<&'a Value, DeserializeError> {
+    match value {
+        Value::Object(object) => object
+            .get(field)
+            .ok_or_else(|| DeserializeError::MissingField(field.to_string())),
+        other => Err(type_mismatch("object", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_value, get_field, DeserializeError, FromValue};
+    use crate::tokenize::Number;
+    use crate::{Object, Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn deserializes_bool() {
+        assert_eq!(from_value::<bool>(&Value::Boolean(true)), Ok(true));
+    }
+
+    #[test]
+    fn deserializes_f64_from_integer_or_float() {
+        assert_eq!(from_value::<f64>(&Value::Number(Number::Integer(10))), Ok(10.0));
+        assert_eq!(from_value::<f64>(&Value::Number(Number::Float(1.5))), Ok(1.5));
+    }
+
+    #[test]
+    fn deserializes_string() {
+        assert_eq!(
+            from_value::<String>(&Value::string("hi")),
+            Ok("hi".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_type_mismatch() {
+        let actual = from_value::<bool>(&Value::Null);
+        assert_eq!(
+            actual,
+            Err(DeserializeError::TypeMismatch {
+                expected: "boolean",
+                found: "null",
+            })
+        );
+    }
+
+    #[test]
+    fn deserializes_option_some_and_none() {
+        assert_eq!(
+            from_value::<Option<bool>>(&Value::Boolean(false)),
+            Ok(Some(false))
+        );
+        assert_eq!(from_value::<Option<bool>>(&Value::Null), Ok(None));
+    }
+
+    #[test]
+    fn deserializes_vec() {
+        let value = Value::Array(vec![Value::string("a"), Value::string("b")]);
+        assert_eq!(
+            from_value::<Vec<String>>(&value),
+            Ok(vec!["a".to_string(), "b".to_string()])
+        );
+    }
+
+    #[test]
+    fn deserializes_hash_map() {
+        let value = Value::Object(Object::from([("key".to_string(), Value::Boolean(true))]));
+        let actual = from_value::<HashMap<String, bool>>(&value).unwrap();
+        assert_eq!(actual.get("key"), Some(&true));
+    }
+
+    #[test]
+    fn get_field_returns_named_value() {
+        let value = Value::Object(Object::from([("name".to_string(), Value::string("ken"))]));
+        let field = get_field(&value, "name").unwrap();
+        assert_eq!(String::from_value(field), Ok("ken".to_string()));
+    }
+
+    #[test]
+    fn get_field_reports_missing_field() {
+        let value = Value::Object(Object::new());
+        assert_eq!(
+            get_field(&value, "name"),
+            Err(DeserializeError::MissingField("name".to_string()))
+        );
+    }
+}
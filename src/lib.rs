@@ -2,23 +2,70 @@ use std::error::Error;
 use std::fmt;
 use std::fs;
 
+pub mod deserialize;
+mod object;
+mod parse;
+pub mod position;
+pub mod serialize;
 mod tokenize;
-pub struct Config {
-    file_path: String,
+
+pub use object::Object;
+pub use parse::TokenParseError;
+pub use position::Position;
+pub use tokenize::{Number, TokenizeError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Tokenize(TokenizeError),
+    Parse(TokenParseError),
 }
-#[derive(Debug, Clone)]
-struct ParseError;
+
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Error while parsing JSON Value")
+        match self {
+            Self::Tokenize(err) => write!(f, "{err}"),
+            Self::Parse(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl ParseError {
+    /// The source `Position` this error points at.
+    pub fn position(&self) -> Position {
+        match self {
+            Self::Tokenize(err) => err.position(),
+            Self::Parse(err) => err.position(),
+        }
+    }
+
+    /// Renders a two-line caret diagnostic pointing at this error's
+    /// location within `input`.
+    pub fn render(&self, input: &str) -> String {
+        position::render_caret(input, self.position())
     }
 }
-enum Value {
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
     Null,
-    True,
-    False,
+    Boolean(bool),
+    Number(Number),
     String(String),
-    Number(f64),
+    Array(Vec<Value>),
+    Object(Object),
+}
+
+#[cfg(test)]
+impl Value {
+    pub fn string(input: &str) -> Self {
+        Self::String(String::from(input))
+    }
+}
+
+pub struct Config {
+    file_path: String,
 }
 
 impl Config {
@@ -31,21 +78,52 @@ impl Config {
     }
 }
 
+/// Reads `config.file_path`, parses it as JSON, and prints the
+/// re-serialized `Value` back out.
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
     let contents = fs::read_to_string(config.file_path)?;
-    println!("{contents}");
+    let value = parse(&contents)?;
+    println!("{}", serialize::to_string(&value));
     Ok(())
 }
+
+/// Tokenizes and parses `input` into a `Value`.
 pub fn parse(input: &str) -> Result<Value, ParseError> {
-    Ok(Value::String(String::from("diwj")))
-}
-
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//     #[test]
-//     fn parse_string() {
-//         let string_value = "\"test\"";
-//         assert_eq!(Ok(Value::String("test".to_string())), parse(&string_value));
-//     }
-// }
+    let tokens = tokenize::tokenize(input).map_err(ParseError::Tokenize)?;
+    parse::parse_tokens(&tokens, &mut 0).map_err(ParseError::Parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_string() {
+        assert_eq!(parse(r#""test""#), Ok(Value::string("test")));
+    }
+
+    #[test]
+    fn parses_nested_structure() {
+        let expected = Value::Object(Object::from([(
+            "key".to_string(),
+            Value::Array(vec![Value::Number(Number::Integer(1)), Value::Boolean(true)]),
+        )]));
+        assert_eq!(parse(r#"{"key": [1, true]}"#), Ok(expected));
+    }
+
+    #[test]
+    fn reports_tokenize_errors() {
+        assert!(matches!(parse("&"), Err(ParseError::Tokenize(_))));
+    }
+
+    #[test]
+    fn renders_caret_diagnostic_for_errors() {
+        let err = parse("&").unwrap_err();
+        assert_eq!(err.render("&"), "&\n^");
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert!(matches!(parse("[1,"), Err(ParseError::Parse(_))));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::Value;
+
+/// A JSON object that preserves the order fields were inserted in, so a
+/// parse -> serialize round trip keeps the author's field order instead of
+/// scrambling it the way `HashMap` would.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    entries: Vec<(String, Value)>,
+}
+
+/// Returned by `insert` when `key` is already present in the `Object`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DuplicateKey(pub String);
+
+impl Object {
+    pub fn new() -> Self {
+        Object { entries: Vec::new() }
+    }
+
+    /// Inserts `key`/`value`, appending it to the end of the field order.
+    /// Fails with `DuplicateKey` if `key` is already present, rather than
+    /// silently overwriting the earlier value.
+    pub fn insert(&mut self, key: String, value: Value) -> Result<(), DuplicateKey> {
+        if self.entries.iter().any(|(k, _)| *k == key) {
+            return Err(DuplicateKey(key));
+        }
+        self.entries.push((key, value));
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl Default for Object {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> From<[(String, Value); N]> for Object {
+    /// Builds an `Object` from an array literal, mirroring `HashMap::from`.
+    /// Panics on a duplicate key, since array literals are caller-authored
+    /// and a duplicate there is a programmer error, not recoverable input.
+    fn from(entries: [(String, Value); N]) -> Self {
+        let mut object = Object::new();
+        for (key, value) in entries {
+            object
+                .insert(key, value)
+                .expect("duplicate key in Object literal");
+        }
+        object
+    }
+}
+
+impl<'a> IntoIterator for &'a Object {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Box<dyn Iterator<Item = (&'a String, &'a Value)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DuplicateKey, Object};
+    use crate::Value;
+
+    #[test]
+    fn inserts_and_gets() {
+        let mut object = Object::new();
+        object.insert("key".to_string(), Value::Null).unwrap();
+        assert_eq!(object.get("key"), Some(&Value::Null));
+        assert_eq!(object.get("missing"), None);
+    }
+
+    #[test]
+    fn preserves_insertion_order() {
+        let mut object = Object::new();
+        object.insert("b".to_string(), Value::Null).unwrap();
+        object.insert("a".to_string(), Value::Null).unwrap();
+        let keys: Vec<&str> = object.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn rejects_duplicate_key() {
+        let mut object = Object::new();
+        object.insert("key".to_string(), Value::Null).unwrap();
+        let actual = object.insert("key".to_string(), Value::Boolean(true));
+        assert_eq!(actual, Err(DuplicateKey("key".to_string())));
+    }
+}
@@ -1,69 +1,118 @@
-use std::collections::HashMap;
 use std::fmt;
 
-use crate::Value;
+use crate::position::{Position, Spanned};
 use crate::tokenize::Token;
+use crate::{Object, Value};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TokenParseError {
-    UnfinishedEscape,
-    InvalidHexDigit,
-    InvalidCodePointValue,
-    ExpectedComma,
-    ExpectedColon,
-    ExpectedProperty,
+    UnfinishedEscape(Position),
+    InvalidHexDigit(Position),
+    InvalidCodePointValue(Position),
+    ExpectedComma(Position),
+    ExpectedColon(Position),
+    ExpectedProperty(Position),
+    DuplicateKey(String, Position),
+    UnexpectedToken(Position),
+    UnexpectedEof(Position),
 }
 
 impl fmt::Display for TokenParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::UnfinishedEscape => write!(f, "UnfinishedEscape"),
-            Self::InvalidHexDigit => write!(f, "InvalidHexDigit"),
-            Self::InvalidCodePointValue => write!(f, "InvalidCodePointValue"),
-            Self::ExpectedComma => write!(f, "ExpectedComma"),
-            Self::ExpectedColon => write!(f, "ExpectedColon"),
-            Self::ExpectedProperty => write!(f, "ExpectedProperty"),
+            Self::UnfinishedEscape(pos) => {
+                write!(f, "UnfinishedEscape at {}:{}", pos.line, pos.column)
+            }
+            Self::InvalidHexDigit(pos) => {
+                write!(f, "InvalidHexDigit at {}:{}", pos.line, pos.column)
+            }
+            Self::InvalidCodePointValue(pos) => {
+                write!(f, "InvalidCodePointValue at {}:{}", pos.line, pos.column)
+            }
+            Self::ExpectedComma(pos) => write!(f, "ExpectedComma at {}:{}", pos.line, pos.column),
+            Self::ExpectedColon(pos) => write!(f, "ExpectedColon at {}:{}", pos.line, pos.column),
+            Self::ExpectedProperty(pos) => {
+                write!(f, "ExpectedProperty at {}:{}", pos.line, pos.column)
+            }
+            Self::DuplicateKey(key, pos) => {
+                write!(f, "DuplicateKey({key}) at {}:{}", pos.line, pos.column)
+            }
+            Self::UnexpectedToken(pos) => {
+                write!(f, "UnexpectedToken at {}:{}", pos.line, pos.column)
+            }
+            Self::UnexpectedEof(pos) => write!(f, "UnexpectedEof at {}:{}", pos.line, pos.column),
+        }
+    }
+}
+
+impl TokenParseError {
+    /// The source `Position` this error points at, e.g. for rendering a
+    /// caret diagnostic with `position::render_caret`.
+    pub fn position(&self) -> Position {
+        match self {
+            Self::UnfinishedEscape(pos)
+            | Self::InvalidHexDigit(pos)
+            | Self::InvalidCodePointValue(pos)
+            | Self::ExpectedComma(pos)
+            | Self::ExpectedColon(pos)
+            | Self::ExpectedProperty(pos)
+            | Self::UnexpectedToken(pos)
+            | Self::UnexpectedEof(pos) => *pos,
+            Self::DuplicateKey(_, pos) => *pos,
         }
     }
 }
+
 type ParseResult = Result<Value, TokenParseError>;
 
-pub fn parse_tokens(tokens: &[Token], index: &mut usize) -> ParseResult {
-    let mut token = &tokens[*index];
+/// Looks up `tokens[index]` without panicking on truncated input, reporting
+/// an `UnexpectedEof` at the position of the last token seen instead.
+fn get_spanned(
+    tokens: &[Spanned<Token>],
+    index: usize,
+) -> Result<&Spanned<Token>, TokenParseError> {
+    tokens.get(index).ok_or_else(|| {
+        let pos = tokens.last().map(|t| t.pos).unwrap_or_else(Position::start);
+        TokenParseError::UnexpectedEof(pos)
+    })
+}
+
+pub fn parse_tokens(tokens: &[Spanned<Token>], index: &mut usize) -> ParseResult {
+    let spanned = get_spanned(tokens, *index)?;
     if matches!(
-        token,
+        spanned.token,
         Token::Null | Token::True | Token::False | Token::Number(_) | Token::String(_)
     ) {
         *index += 1;
     }
-    match token {
+    match &spanned.token {
         Token::Null => Ok(Value::Null),
         Token::True => Ok(Value::Boolean(true)),
         Token::False => Ok(Value::Boolean(false)),
         Token::Number(number) => Ok(Value::Number(*number)),
-        Token::String(string) => parse_string(string),
+        Token::String(string) => parse_string(string, spanned.pos),
         Token::SLeftBracket => parse_array(tokens, index),
         Token::CLeftBracket => parse_object(tokens, index),
-        _ => todo!(),
+        _ => Err(TokenParseError::UnexpectedToken(spanned.pos)),
     }
 }
-fn parse_array(tokens: &[Token], index: &mut usize) -> ParseResult {
-    debug_assert!(tokens[*index] == Token::SLeftBracket);
+fn parse_array(tokens: &[Spanned<Token>], index: &mut usize) -> ParseResult {
+    debug_assert!(tokens[*index].token == Token::SLeftBracket);
     let mut array: Vec<Value> = Vec::new();
     loop {
         // consume left bracket
         *index += 1;
-        if tokens[*index] == Token::SRightBracket {
+        if get_spanned(tokens, *index)?.token == Token::SRightBracket {
             break;
         }
         let value = parse_tokens(tokens, index)?;
         array.push(value);
 
-        let token = &tokens[*index];
-        match token {
+        let spanned = get_spanned(tokens, *index)?;
+        match spanned.token {
             Token::Comma => {}
             Token::SRightBracket => break,
-            _ => return Err(TokenParseError::ExpectedComma),
+            _ => return Err(TokenParseError::ExpectedComma(spanned.pos)),
         }
     }
     *index += 1;
@@ -71,41 +120,51 @@ fn parse_array(tokens: &[Token], index: &mut usize) -> ParseResult {
     Ok(Value::Array(array))
 }
 
-fn parse_object(tokens: &[Token], index: &mut usize) -> ParseResult {
-    debug_assert!(tokens[*index] == Token::CLeftBracket);
-    let mut object: HashMap<String, Value> = HashMap::new();
+fn parse_object(tokens: &[Spanned<Token>], index: &mut usize) -> ParseResult {
+    debug_assert!(tokens[*index].token == Token::CLeftBracket);
+    let mut object = Object::new();
 
     loop {
         *index += 1;
-        if tokens[*index] == Token::CRightBracket {
+        if get_spanned(tokens, *index)?.token == Token::CRightBracket {
             break;
         }
-        if let Token::String(s) = &tokens[*index] {
+        if let Token::String(s) = &get_spanned(tokens, *index)?.token {
+            let key = s.clone();
+            let key_pos = get_spanned(tokens, *index)?.pos;
             *index += 1;
-            if Token::Colon == tokens[*index] {
+            if Token::Colon == get_spanned(tokens, *index)?.token {
                 *index += 1;
 
-                let key = s.clone();
                 let value = parse_tokens(tokens, index)?;
-                object.insert(key, value);
+                object
+                    .insert(key, value)
+                    .map_err(|dup| TokenParseError::DuplicateKey(dup.0, key_pos))?;
             } else {
-                return Err(TokenParseError::ExpectedColon);
+                return Err(TokenParseError::ExpectedColon(
+                    get_spanned(tokens, *index)?.pos,
+                ));
             }
-            match &tokens[*index] {
+            match &get_spanned(tokens, *index)?.token {
                 Token::Comma => {}
                 Token::CRightBracket => break,
-                _ => return Err(TokenParseError::ExpectedComma),
+                _ => {
+                    return Err(TokenParseError::ExpectedComma(
+                        get_spanned(tokens, *index)?.pos,
+                    ))
+                }
             }
         } else {
-            return Err(TokenParseError::ExpectedProperty);
+            return Err(TokenParseError::ExpectedProperty(
+                get_spanned(tokens, *index)?.pos,
+            ));
         }
     }
     *index += 1;
-    print! {"obj {:?}", object}
     Ok(Value::Object(object))
 }
 
-fn parse_string(s: &str) -> ParseResult {
+fn parse_string(s: &str, pos: Position) -> ParseResult {
     let mut chars = s.chars();
     let mut output = String::with_capacity(s.len());
     let mut is_escaping = false;
@@ -123,14 +182,15 @@ fn parse_string(s: &str) -> ParseResult {
                 'u' => {
                     let mut sum = 0;
                     for i in 0..4 {
-                        let next_char = chars.next().ok_or(TokenParseError::UnfinishedEscape)?;
+                        let next_char =
+                            chars.next().ok_or(TokenParseError::UnfinishedEscape(pos))?;
                         let digit = next_char
                             .to_digit(16)
-                            .ok_or(TokenParseError::InvalidHexDigit)?;
+                            .ok_or(TokenParseError::InvalidHexDigit(pos))?;
                         sum += (16u32).pow(3 - i) * digit;
                     }
-                    let unescaped_char =
-                        char::from_u32(sum).ok_or(TokenParseError::InvalidCodePointValue)?;
+                    let unescaped_char = char::from_u32(sum)
+                        .ok_or(TokenParseError::InvalidCodePointValue(pos))?;
                     output.push(unescaped_char);
                 }
                 _ => output.push(next_char),
@@ -142,74 +202,84 @@ fn parse_string(s: &str) -> ParseResult {
             output.push(next_char);
         }
     }
-    return Ok(Value::String(output));
+    Ok(Value::String(output))
 }
 
 #[cfg(test)]
 mod tests {
 
-    use std::collections::HashMap;
+    use crate::position::{Position, Spanned};
+    use crate::tokenize::{Number, Token};
+    use crate::{Object, Value};
 
-    use crate::Value;
-    use crate::tokenize::Token;
+    use super::{parse_tokens, TokenParseError};
 
-    use super::parse_tokens;
+    fn spanned(token: Token) -> Spanned<Token> {
+        Spanned {
+            token,
+            pos: Position::start(),
+        }
+    }
 
-    fn check(input: &[Token], expected: Value) {
-        let actual = parse_tokens(&input, &mut 0).unwrap();
+    fn check(input: &[Spanned<Token>], expected: Value) {
+        let actual = parse_tokens(input, &mut 0).unwrap();
         assert_eq!(actual, expected);
     }
     #[test]
     fn parses_null() {
-        let input = [Token::Null];
+        let input = [spanned(Token::Null)];
         let expected = Value::Null;
         check(&input, expected);
     }
     #[test]
     fn parses_true() {
-        let input = [Token::True];
+        let input = [spanned(Token::True)];
         let expected = Value::Boolean(true);
 
         check(&input, expected);
     }
     #[test]
     fn parses_false() {
-        let input = [Token::False];
+        let input = [spanned(Token::False)];
         let expected = Value::Boolean(false);
 
         check(&input, expected);
     }
     #[test]
     fn parses_number() {
-        let input = [Token::Number(10_f64)];
-        let expected = Value::Number(10_f64);
+        let input = [spanned(Token::Number(Number::Integer(10)))];
+        let expected = Value::Number(Number::Integer(10));
 
         check(&input, expected);
     }
     #[test]
     fn parses_string() {
-        let input = [Token::string("test")];
+        let input = [spanned(Token::string("test"))];
         let expected = Value::string("test");
 
         check(&input, expected);
     }
     #[test]
     fn parses_string_with_escapes() {
-        let input = [Token::string(r#""test \" ""#)];
+        let input = [spanned(Token::string(r#""test \" ""#))];
         let expected = Value::string(r#""test " ""#);
 
         check(&input, expected);
     }
     #[test]
     fn parses_string_with_unicodes() {
-        let input = [Token::string(r#""test \u002F ""#)];
+        let input = [spanned(Token::string(r#""test \u002F ""#))];
         let expected = Value::string(r#""test / ""#);
 
         check(&input, expected);
     }
     #[test]
     fn parses_array_simple() {
-        let input = [Token::SLeftBracket, Token::False, Token::SRightBracket];
+        let input = [
+            spanned(Token::SLeftBracket),
+            spanned(Token::False),
+            spanned(Token::SRightBracket),
+        ];
         let expected = Value::Array(vec![Value::Boolean(false)]);
 
         check(&input, expected);
@@ -217,19 +287,22 @@ mod tests {
     #[test]
     fn parses_array() {
         let input = [
-            Token::SLeftBracket,
-            Token::False,
-            Token::Comma,
-            Token::Number(20_f64),
-            Token::SRightBracket,
+            spanned(Token::SLeftBracket),
+            spanned(Token::False),
+            spanned(Token::Comma),
+            spanned(Token::Number(Number::Integer(20))),
+            spanned(Token::SRightBracket),
         ];
-        let expected = Value::Array(vec![Value::Boolean(false), Value::Number(20_f64)]);
+        let expected = Value::Array(vec![
+            Value::Boolean(false),
+            Value::Number(Number::Integer(20)),
+        ]);
 
         check(&input, expected);
     }
     #[test]
     fn parses_empty_array() {
-        let input = [Token::SLeftBracket, Token::SRightBracket];
+        let input = [spanned(Token::SLeftBracket), spanned(Token::SRightBracket)];
         let expected = Value::Array(vec![]);
 
         check(&input, expected);
@@ -237,61 +310,102 @@ mod tests {
     #[test]
     fn parses_array_in_array() {
         let input = [
-            Token::SLeftBracket,
-            Token::SLeftBracket,
-            Token::False,
-            Token::Comma,
-            Token::Number(20_f64),
-            Token::SRightBracket,
-            Token::SRightBracket,
+            spanned(Token::SLeftBracket),
+            spanned(Token::SLeftBracket),
+            spanned(Token::False),
+            spanned(Token::Comma),
+            spanned(Token::Number(Number::Integer(20))),
+            spanned(Token::SRightBracket),
+            spanned(Token::SRightBracket),
         ];
         let expected = Value::Array(vec![Value::Array(vec![
             Value::Boolean(false),
-            Value::Number(20_f64),
+            Value::Number(Number::Integer(20)),
         ])]);
 
         check(&input, expected);
     }
     #[test]
     fn parses_empty_obj() {
-        let input = [Token::CLeftBracket, Token::CRightBracket];
-        let expected = Value::Object(HashMap::new());
+        let input = [spanned(Token::CLeftBracket), spanned(Token::CRightBracket)];
+        let expected = Value::Object(Object::new());
 
         check(&input, expected);
     }
     #[test]
     fn parses_obj() {
         let input = [
-            Token::CLeftBracket,
-            Token::string("test_key"),
-            Token::Colon,
-            Token::Null,
-            Token::CRightBracket,
+            spanned(Token::CLeftBracket),
+            spanned(Token::string("test_key")),
+            spanned(Token::Colon),
+            spanned(Token::Null),
+            spanned(Token::CRightBracket),
         ];
-        let expected = Value::Object(HashMap::from([("test_key".into(), Value::Null)]));
+        let expected = Value::Object(Object::from([("test_key".into(), Value::Null)]));
 
         check(&input, expected);
     }
     #[test]
     fn parses_obj_in_obj() {
         let input = [
-            Token::CLeftBracket,
-            Token::string("test_key"),
-            Token::Colon,
-            Token::CLeftBracket,
-            Token::string("test_key"),
-            Token::Colon,
-            Token::string("test_value_inside"),
-            Token::CRightBracket,
-            Token::CRightBracket,
+            spanned(Token::CLeftBracket),
+            spanned(Token::string("test_key")),
+            spanned(Token::Colon),
+            spanned(Token::CLeftBracket),
+            spanned(Token::string("test_key")),
+            spanned(Token::Colon),
+            spanned(Token::string("test_value_inside")),
+            spanned(Token::CRightBracket),
+            spanned(Token::CRightBracket),
         ];
 
-        let expected_inside = Value::Object(HashMap::from([(
+        let expected_inside = Value::Object(Object::from([(
             "test_key".into(),
             Value::string("test_value_inside"),
         )]));
-        let expected = Value::Object(HashMap::from([("test_key".into(), expected_inside)]));
+        let expected = Value::Object(Object::from([("test_key".into(), expected_inside)]));
 
         check(&input, expected);
     }
+    #[test]
+    fn rejects_duplicate_key_in_object() {
+        let input = [
+            spanned(Token::CLeftBracket),
+            spanned(Token::string("test_key")),
+            spanned(Token::Colon),
+            spanned(Token::Null),
+            spanned(Token::Comma),
+            spanned(Token::string("test_key")),
+            spanned(Token::Colon),
+            spanned(Token::True),
+            spanned(Token::CRightBracket),
+        ];
+        let expected = Err(TokenParseError::DuplicateKey(
+            "test_key".to_string(),
+            Position::start(),
+        ));
+
+        let actual = parse_tokens(&input, &mut 0);
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn rejects_unexpected_token_in_value_position() {
+        let input = [spanned(Token::Comma)];
+        let expected = Err(TokenParseError::UnexpectedToken(Position::start()));
+
+        let actual = parse_tokens(&input, &mut 0);
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn truncated_array_reports_eof_instead_of_panicking() {
+        let input = [
+            spanned(Token::SLeftBracket),
+            spanned(Token::Number(Number::Integer(1))),
+            spanned(Token::Comma),
+        ];
+        let expected = Err(TokenParseError::UnexpectedEof(Position::start()));
+
+        let actual = parse_tokens(&input, &mut 0);
+        assert_eq!(actual, expected);
+    }
 }
@@ -0,0 +1,66 @@
+/// A location in the original source text, used to point at the offending
+/// spot in a parse or tokenize error.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    /// Byte offset into the source text, not a `char` index, so it can be
+    /// used to slice back into the original `&str`.
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn start() -> Self {
+        Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+/// A value tagged with the `Position` at which it starts in the source text.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Spanned<T> {
+    pub token: T,
+    pub pos: Position,
+}
+
+/// Renders a two-line caret diagnostic for `pos` within `input`: the source
+/// line the position falls on, followed by a `^` under the offending column.
+pub fn render_caret(input: &str, pos: Position) -> String {
+    let source_line = input.lines().nth(pos.line.saturating_sub(1)).unwrap_or("");
+    let mut caret_line = String::new();
+    for _ in 0..pos.column.saturating_sub(1) {
+        caret_line.push(' ');
+    }
+    caret_line.push('^');
+    format!("{source_line}\n{caret_line}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_caret, Position};
+
+    #[test]
+    fn renders_caret_on_first_line() {
+        let pos = Position {
+            offset: 2,
+            line: 1,
+            column: 3,
+        };
+        let rendered = render_caret("[1,", pos);
+        assert_eq!(rendered, "[1,\n  ^");
+    }
+
+    #[test]
+    fn renders_caret_on_second_line() {
+        let pos = Position {
+            offset: 5,
+            line: 2,
+            column: 2,
+        };
+        let rendered = render_caret("[1,\n2]", pos);
+        assert_eq!(rendered, "2]\n ^");
+    }
+}
@@ -0,0 +1,197 @@
+use crate::tokenize::Number;
+use crate::Value;
+
+/// Serializes a `Value` back into compact JSON text.
+pub fn to_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(value, &mut out);
+    out
+}
+
+/// Serializes a `Value` into JSON text, with `indent` spaces per nesting
+/// level for arrays and objects.
+pub fn to_string_pretty(value: &Value, indent: usize) -> String {
+    let mut out = String::new();
+    write_value_pretty(value, &mut out, indent, 0);
+    out
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Boolean(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&format_number(n)),
+        Value::String(s) => write_escaped_string(s, out),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(object) => {
+            out.push('{');
+            for (i, (key, val)) in object.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_escaped_string(key, out);
+                out.push(':');
+                write_value(val, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_value_pretty(value: &Value, out: &mut String, indent: usize, depth: usize) {
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                push_indent(out, indent, depth + 1);
+                write_value_pretty(item, out, indent, depth + 1);
+                if i + 1 < items.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            push_indent(out, indent, depth);
+            out.push(']');
+        }
+        Value::Object(object) if !object.is_empty() => {
+            out.push_str("{\n");
+            for (i, (key, val)) in object.iter().enumerate() {
+                push_indent(out, indent, depth + 1);
+                write_escaped_string(key, out);
+                out.push_str(": ");
+                write_value_pretty(val, out, indent, depth + 1);
+                if i + 1 < object.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            push_indent(out, indent, depth);
+            out.push('}');
+        }
+        // Empty arrays/objects and scalars have no nesting to pretty-print.
+        _ => write_value(value, out),
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize, depth: usize) {
+    for _ in 0..(indent * depth) {
+        out.push(' ');
+    }
+}
+
+fn format_number(n: &Number) -> String {
+    match n {
+        Number::Integer(i) => i.to_string(),
+        Number::Float(f) => format!("{f}"),
+    }
+}
+
+fn write_escaped_string(s: &str, out: &mut String) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_string, to_string_pretty};
+    use crate::tokenize::Number;
+    use crate::{Object, Value};
+
+    #[test]
+    fn serializes_null() {
+        assert_eq!(to_string(&Value::Null), "null");
+    }
+
+    #[test]
+    fn serializes_booleans() {
+        assert_eq!(to_string(&Value::Boolean(true)), "true");
+        assert_eq!(to_string(&Value::Boolean(false)), "false");
+    }
+
+    #[test]
+    fn serializes_integral_number_without_trailing_zero() {
+        assert_eq!(to_string(&Value::Number(Number::Integer(10))), "10");
+    }
+
+    #[test]
+    fn serializes_fractional_number() {
+        assert_eq!(to_string(&Value::Number(Number::Float(10.5))), "10.5");
+    }
+
+    #[test]
+    fn serializes_string_with_escapes() {
+        let value = Value::string("line\nbreak\t\"quote\"");
+        assert_eq!(to_string(&value), r#""line\nbreak\t\"quote\"""#);
+    }
+
+    #[test]
+    fn serializes_control_char_as_unicode_escape() {
+        let value = Value::String("\u{1}".to_string());
+        assert_eq!(to_string(&value), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn serializes_array() {
+        let value = Value::Array(vec![
+            Value::Number(Number::Integer(1)),
+            Value::Boolean(false),
+        ]);
+        assert_eq!(to_string(&value), "[1,false]");
+    }
+
+    #[test]
+    fn serializes_single_key_object() {
+        let value = Value::Object(Object::from([("key".to_string(), Value::Null)]));
+        assert_eq!(to_string(&value), r#"{"key":null}"#);
+    }
+
+    #[test]
+    fn serializes_object_preserving_insertion_order() {
+        let value = Value::Object(Object::from([
+            ("b".to_string(), Value::Number(Number::Integer(2))),
+            ("a".to_string(), Value::Number(Number::Integer(1))),
+        ]));
+        assert_eq!(to_string(&value), r#"{"b":2,"a":1}"#);
+    }
+
+    #[test]
+    fn pretty_prints_nested_array() {
+        let value = Value::Array(vec![
+            Value::Number(Number::Integer(1)),
+            Value::Number(Number::Integer(2)),
+        ]);
+        assert_eq!(to_string_pretty(&value, 2), "[\n  1,\n  2\n]");
+    }
+
+    #[test]
+    fn pretty_prints_empty_array_inline() {
+        let value = Value::Array(vec![]);
+        assert_eq!(to_string_pretty(&value, 2), "[]");
+    }
+
+    #[test]
+    fn pretty_prints_single_key_object() {
+        let value = Value::Object(Object::from([("key".to_string(), Value::Boolean(true))]));
+        assert_eq!(to_string_pretty(&value, 2), "{\n  \"key\": true\n}");
+    }
+}
@@ -1,16 +1,61 @@
 use std::fmt;
 
-use std::num::ParseFloatError;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::position::{Position, Spanned};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum TokenizeError {
-    UnfinishedLiteralValue,
-    ParseNumberError(ParseFloatError),
-    UnclosedQuotes,
-    UnexpectedEof,
-    CharNotRecognized(char),
+    UnfinishedLiteralValue(Position),
+    InvalidNumber(Position),
+    UnclosedQuotes(Position),
+    UnexpectedEof(Position),
+    CharNotRecognized(char, Position),
+}
+
+impl fmt::Display for TokenizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnfinishedLiteralValue(pos) => {
+                write!(f, "UnfinishedLiteralValue at {}:{}", pos.line, pos.column)
+            }
+            Self::InvalidNumber(pos) => write!(f, "InvalidNumber at {}:{}", pos.line, pos.column),
+            Self::UnclosedQuotes(pos) => {
+                write!(f, "UnclosedQuotes at {}:{}", pos.line, pos.column)
+            }
+            Self::UnexpectedEof(pos) => write!(f, "UnexpectedEof at {}:{}", pos.line, pos.column),
+            Self::CharNotRecognized(ch, pos) => {
+                write!(f, "CharNotRecognized({ch}) at {}:{}", pos.line, pos.column)
+            }
+        }
+    }
+}
+
+impl TokenizeError {
+    /// The source `Position` this error points at, e.g. for rendering a
+    /// caret diagnostic with `position::render_caret`.
+    pub fn position(&self) -> Position {
+        match self {
+            Self::UnfinishedLiteralValue(pos)
+            | Self::InvalidNumber(pos)
+            | Self::UnclosedQuotes(pos)
+            | Self::UnexpectedEof(pos)
+            | Self::CharNotRecognized(_, pos) => *pos,
+        }
+    }
 }
-#[derive(Debug, PartialEq)]
+
+/// A JSON number, distinguishing literals that had no fraction or exponent
+/// (`Integer`) from ones that did (`Float`), so e.g. large integers survive
+/// a parse -> serialize round trip without being mangled into `1.0e18` form.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Number {
+    Integer(i64),
+    Float(f64),
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     CLeftBracket,
     CRightBracket,
@@ -22,7 +67,7 @@ pub enum Token {
     True,
     False,
     String(String),
-    Number(f64),
+    Number(Number),
 }
 
 #[cfg(test)]
@@ -34,63 +79,129 @@ impl Token {
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            Token::CLeftBracket => write!(f, "{{"),
+            Token::CRightBracket => write!(f, "}}"),
+            Token::SLeftBracket => write!(f, "["),
+            Token::SRightBracket => write!(f, "]"),
+            Token::Comma => write!(f, ","),
+            Token::Colon => write!(f, ":"),
             Token::Null => write!(f, "null"),
             Token::True => write!(f, "true"),
             Token::False => write!(f, "false"),
-            _ => todo!("No string representation yet for {self}"),
+            Token::String(s) => write!(f, "\"{s}\""),
+            Token::Number(Number::Integer(i)) => write!(f, "{i}"),
+            Token::Number(Number::Float(n)) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// A cursor over the source text that never indexes out of bounds: `next`
+/// and `peek` return `Option`, and `expect` turns exhaustion into an
+/// `UnexpectedEof` at the cursor's current `Position`.
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    pos: Position,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(input: &'a str) -> Self {
+        Cursor {
+            chars: input.chars().peekable(),
+            pos: Position::start(),
         }
     }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        self.pos.offset += ch.len_utf8();
+        if ch == '\n' {
+            self.pos.line += 1;
+            self.pos.column = 1;
+        } else {
+            self.pos.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn expect(&mut self) -> Result<char, TokenizeError> {
+        self.next().ok_or(TokenizeError::UnexpectedEof(self.pos))
+    }
+
+    fn position(&self) -> Position {
+        self.pos
+    }
 }
-pub fn tokenize(input: String) -> Result<Vec<Token>, TokenizeError> {
-    let chars: Vec<char> = input.chars().collect();
-    let mut index = 0;
+
+pub fn tokenize(input: &str) -> Result<Vec<Spanned<Token>>, TokenizeError> {
+    let mut cursor = Cursor::new(input);
     let mut tokens = Vec::new();
 
-    while index < chars.len() {
-        let token = make_token(&chars, &mut index)?;
+    while let Some(token) = make_token(&mut cursor)? {
         tokens.push(token);
-
-        index += 1;
     }
     Ok(tokens)
 }
 
-fn make_token(chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeError> {
-    let mut ch = chars[*index];
-
-    while ch.is_ascii_whitespace() {
-        *index += 1;
-        if *index >= chars.len() {
-            return Err(TokenizeError::UnexpectedEof);
-        }
-        ch = chars[*index];
+fn skip_whitespace(cursor: &mut Cursor) {
+    while matches!(cursor.peek(), Some(ch) if ch.is_ascii_whitespace()) {
+        cursor.next();
     }
+}
+
+fn make_token(cursor: &mut Cursor) -> Result<Option<Spanned<Token>>, TokenizeError> {
+    skip_whitespace(cursor);
+    let Some(ch) = cursor.peek() else {
+        return Ok(None);
+    };
+    let pos = cursor.position();
     let token = match ch {
-        '{' => Token::CLeftBracket,
-        '}' => Token::CRightBracket,
-        '[' => Token::SLeftBracket,
-        ']' => Token::SRightBracket,
-        ',' => Token::Comma,
-        ':' => Token::Colon,
-        'n' => tokenize_literal(chars, index, Token::Null)?,
-        't' => tokenize_literal(chars, index, Token::True)?,
-        'f' => tokenize_literal(chars, index, Token::False)?,
-        '"' => tokenize_string(chars, index)?,
-        ch if ch.is_ascii_digit() || ch == '-' => tokenize_float(chars, index)?,
-        ch => return Err(TokenizeError::CharNotRecognized(ch)),
+        '{' => {
+            cursor.next();
+            Token::CLeftBracket
+        }
+        '}' => {
+            cursor.next();
+            Token::CRightBracket
+        }
+        '[' => {
+            cursor.next();
+            Token::SLeftBracket
+        }
+        ']' => {
+            cursor.next();
+            Token::SRightBracket
+        }
+        ',' => {
+            cursor.next();
+            Token::Comma
+        }
+        ':' => {
+            cursor.next();
+            Token::Colon
+        }
+        'n' => tokenize_literal(cursor, Token::Null)?,
+        't' => tokenize_literal(cursor, Token::True)?,
+        'f' => tokenize_literal(cursor, Token::False)?,
+        '"' => tokenize_string(cursor)?,
+        ch if ch.is_ascii_digit() || ch == '-' => tokenize_number(cursor)?,
+        ch => return Err(TokenizeError::CharNotRecognized(ch, pos)),
     };
-    Ok(token)
+    Ok(Some(Spanned { token, pos }))
 }
-fn tokenize_string(chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeError> {
+
+fn tokenize_string(cursor: &mut Cursor) -> Result<Token, TokenizeError> {
     let mut str = String::new();
     let mut is_escaping = false;
+    cursor.next(); // consume the opening quote
 
     loop {
-        *index += 1;
-        if *index >= chars.len() {
-            return Err(TokenizeError::UnclosedQuotes);
-        }
-        let ch = chars[*index];
+        let ch = cursor
+            .next()
+            .ok_or(TokenizeError::UnclosedQuotes(cursor.position()))?;
         match ch {
             '"' if !is_escaping => break,
             '\\' => is_escaping = !is_escaping,
@@ -101,152 +212,292 @@ fn tokenize_string(chars: &Vec<char>, index: &mut usize) -> Result<Token, Tokeni
     Ok(Token::String(str))
 }
 
-fn tokenize_literal(
-    chars: &Vec<char>,
-    index: &mut usize,
-    token: Token,
-) -> Result<Token, TokenizeError> {
+fn tokenize_literal(cursor: &mut Cursor, token: Token) -> Result<Token, TokenizeError> {
     for expected_char in token.to_string().chars() {
-        if expected_char != chars[*index] {
-            return Err(TokenizeError::UnfinishedLiteralValue);
+        let actual = cursor.expect()?;
+        if actual != expected_char {
+            return Err(TokenizeError::UnfinishedLiteralValue(cursor.position()));
         }
-        *index += 1;
     }
-    *index -= 1;
     Ok(token)
 }
-fn tokenize_float(chars: &Vec<char>, index: &mut usize) -> Result<Token, TokenizeError> {
-    let mut unparsed_num = String::new();
-    let mut has_decimal = false;
 
-    if chars[*index] == '-' {
-        unparsed_num.push(chars[*index]);
-        *index += 1;
+/// Consumes a digit run and appends it to `literal`. Returns how many
+/// digits were consumed.
+fn consume_digits(cursor: &mut Cursor, literal: &mut String) -> usize {
+    let mut count = 0;
+    while matches!(cursor.peek(), Some(ch) if ch.is_ascii_digit()) {
+        literal.push(cursor.next().expect("peeked char must be present"));
+        count += 1;
     }
-    while *index < chars.len() {
-        let ch = chars[*index];
+    count
+}
 
-        match ch {
-            ch if ch.is_ascii_digit() => unparsed_num.push(ch),
-            ch if ch == '.' && !has_decimal => {
-                has_decimal = true;
-                unparsed_num.push(ch)
+/// Tokenizes the full JSON number grammar: an optional `-`, an integer part
+/// that is either `0` or `[1-9][0-9]*`, an optional `.` fraction with at
+/// least one digit, and an optional `[eE][+-]?` exponent with at least one
+/// digit.
+fn tokenize_number(cursor: &mut Cursor) -> Result<Token, TokenizeError> {
+    let start = cursor.position();
+    let mut literal = String::new();
+
+    if cursor.peek() == Some('-') {
+        literal.push(cursor.next().expect("peeked char must be present"));
+    }
+
+    match cursor.peek() {
+        Some('0') => {
+            literal.push(cursor.next().expect("peeked char must be present"));
+            if matches!(cursor.peek(), Some(ch) if ch.is_ascii_digit()) {
+                return Err(TokenizeError::InvalidNumber(start));
             }
-            _ => break,
         }
-        *index += 1;
+        Some(ch) if ch.is_ascii_digit() => {
+            consume_digits(cursor, &mut literal);
+        }
+        _ => return Err(TokenizeError::InvalidNumber(start)),
     }
-    *index -= 1;
-    match unparsed_num.parse() {
-        Ok(f) => Ok(Token::Number(f)),
-        Err(err) => Err(TokenizeError::ParseNumberError(err)),
+
+    let mut is_float = false;
+
+    if cursor.peek() == Some('.') {
+        is_float = true;
+        literal.push(cursor.next().expect("peeked char must be present"));
+        if consume_digits(cursor, &mut literal) == 0 {
+            return Err(TokenizeError::InvalidNumber(start));
+        }
+    }
+
+    if matches!(cursor.peek(), Some('e') | Some('E')) {
+        is_float = true;
+        literal.push(cursor.next().expect("peeked char must be present"));
+        if matches!(cursor.peek(), Some('+') | Some('-')) {
+            literal.push(cursor.next().expect("peeked char must be present"));
+        }
+        if consume_digits(cursor, &mut literal) == 0 {
+            return Err(TokenizeError::InvalidNumber(start));
+        }
     }
+
+    let number = if is_float {
+        literal
+            .parse::<f64>()
+            .map(Number::Float)
+            .map_err(|_| TokenizeError::InvalidNumber(start))?
+    } else {
+        literal
+            .parse::<i64>()
+            .map(Number::Integer)
+            .or_else(|_| literal.parse::<f64>().map(Number::Float))
+            .map_err(|_| TokenizeError::InvalidNumber(start))?
+    };
+
+    Ok(Token::Number(number))
 }
 
 #[cfg(test)]
 mod tests {
 
+    use crate::position::Position;
     use crate::tokenize::TokenizeError;
 
-    use super::{Token, tokenize};
+    use super::{tokenize, Number, Token};
+
+    fn tokens(input: &str) -> Vec<Token> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .collect()
+    }
+
+    #[test]
+    fn displays_every_token_kind() {
+        assert_eq!(Token::CLeftBracket.to_string(), "{");
+        assert_eq!(Token::CRightBracket.to_string(), "}");
+        assert_eq!(Token::SLeftBracket.to_string(), "[");
+        assert_eq!(Token::SRightBracket.to_string(), "]");
+        assert_eq!(Token::Comma.to_string(), ",");
+        assert_eq!(Token::Colon.to_string(), ":");
+        assert_eq!(Token::Null.to_string(), "null");
+        assert_eq!(Token::True.to_string(), "true");
+        assert_eq!(Token::False.to_string(), "false");
+        assert_eq!(Token::string("hi").to_string(), "\"hi\"");
+        assert_eq!(Token::Number(Number::Integer(10)).to_string(), "10");
+        assert_eq!(Token::Number(Number::Float(1.5)).to_string(), "1.5");
+    }
 
     #[test]
     fn just_comma() {
-        let input = String::from(",");
         let expected = [Token::Comma];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        assert_eq!(tokens(","), expected);
     }
     #[test]
     fn true_comma() {
-        let input = String::from("true,");
         let expected = [Token::True, Token::Comma];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        assert_eq!(tokens("true,"), expected);
     }
 
     #[test]
     fn just_null() {
-        let input = String::from("null");
         let expected = [Token::Null];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        assert_eq!(tokens("null"), expected);
     }
     #[test]
     fn just_true() {
-        let input = String::from("true");
         let expected = [Token::True];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        assert_eq!(tokens("true"), expected);
     }
     #[test]
     fn just_false() {
-        let input = String::from("false");
         let expected = [Token::False];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        assert_eq!(tokens("false"), expected);
     }
     #[test]
     fn integer() {
-        let input = String::from("123");
-        let expected = [Token::Number(123.0)];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        let expected = [Token::Number(Number::Integer(123))];
+        assert_eq!(tokens("123"), expected);
     }
     #[test]
     fn floating_point() {
-        let input = String::from("123.123");
-        let expected = [Token::Number(123.123)];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        let expected = [Token::Number(Number::Float(123.123))];
+        assert_eq!(tokens("123.123"), expected);
     }
     #[test]
     fn negative_number() {
-        let input = String::from("-12");
-
-        let expected = [Token::Number(-12.0)];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        let expected = [Token::Number(Number::Integer(-12))];
+        assert_eq!(tokens("-12"), expected);
+    }
+    #[test]
+    fn zero() {
+        let expected = [Token::Number(Number::Integer(0))];
+        assert_eq!(tokens("0"), expected);
+    }
+    #[test]
+    fn rejects_leading_zero() {
+        let expected = Err(TokenizeError::InvalidNumber(Position::start()));
+        assert_eq!(tokenize("01"), expected);
+    }
+    #[test]
+    fn lowercase_exponent() {
+        let expected = [Token::Number(Number::Float(1e10))];
+        assert_eq!(tokens("1e10"), expected);
+    }
+    #[test]
+    fn uppercase_exponent_with_sign() {
+        let expected = [Token::Number(Number::Float(2.5E-3))];
+        assert_eq!(tokens("2.5E-3"), expected);
+    }
+    #[test]
+    fn fraction_and_exponent() {
+        let expected = [Token::Number(Number::Float(6.022e23))];
+        assert_eq!(tokens("6.022e23"), expected);
+    }
+    #[test]
+    fn rejects_fraction_without_digits() {
+        let expected = Err(TokenizeError::InvalidNumber(Position::start()));
+        assert_eq!(tokenize("1."), expected);
+    }
+    #[test]
+    fn rejects_exponent_without_digits() {
+        let expected = Err(TokenizeError::InvalidNumber(Position::start()));
+        assert_eq!(tokenize("1e"), expected);
+    }
+    #[test]
+    fn large_integer_stays_integral() {
+        let expected = [Token::Number(Number::Integer(123456789012345))];
+        assert_eq!(tokens("123456789012345"), expected);
     }
     #[test]
     fn just_ken() {
-        let input = String::from("\"ken\"");
-
         let expected = [Token::string("ken")];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected);
+        assert_eq!(tokens(r#""ken""#), expected);
     }
     #[test]
     fn just_ken_bad() {
-        let input = String::from("\"ken");
-        let expected = Err(TokenizeError::UnclosedQuotes);
+        let expected = Err(TokenizeError::UnclosedQuotes(Position {
+            offset: 4,
+            line: 1,
+            column: 5,
+        }));
 
-        let actual = tokenize(input);
+        let actual = tokenize("\"ken");
         assert_eq!(actual, expected)
     }
     #[test]
     fn escaped_quote() {
-        let input = String::from(r#""this is \" escaped""#);
         let expected = [Token::string(r#"this is \" escaped"#)];
-
-        let actual = tokenize(input).unwrap();
-        assert_eq!(actual, expected)
+        assert_eq!(tokens(r#""this is \" escaped""#), expected)
     }
     #[test]
     fn unkown_char() {
-        let input = String::from(r#"&"#);
-        let expected = Err(TokenizeError::CharNotRecognized('&'));
+        let expected = Err(TokenizeError::CharNotRecognized(
+            '&',
+            Position {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+        ));
 
-        let actual = tokenize(input);
+        let actual = tokenize("&");
         assert_eq!(actual, expected)
     }
+    #[test]
+    fn tracks_position_across_lines() {
+        let actual = tokenize("[1,\n2]").unwrap();
+
+        assert_eq!(
+            actual[3].pos,
+            Position {
+                offset: 4,
+                line: 2,
+                column: 1,
+            }
+        );
+    }
+    #[test]
+    fn tracks_byte_offset_across_multibyte_chars() {
+        // "é" is 2 bytes (`é`.len_utf8() == 2), so the comma after the
+        // closing quote sits at byte offset 4, not char offset 3.
+        let actual = tokenize("\"é\",1").unwrap();
+
+        assert_eq!(
+            actual[1].pos,
+            Position {
+                offset: 4,
+                line: 1,
+                column: 4,
+            }
+        );
+    }
+    #[test]
+    fn truncated_array_tokenizes_without_panicking() {
+        let expected = [
+            Token::SLeftBracket,
+            Token::Number(Number::Integer(1)),
+            Token::Comma,
+        ];
+        assert_eq!(tokens("[1,"), expected);
+    }
+    #[test]
+    fn truncated_literal_reports_eof() {
+        let expected = Err(TokenizeError::UnexpectedEof(Position {
+            offset: 2,
+            line: 1,
+            column: 3,
+        }));
+        let actual = tokenize("nu");
+        assert_eq!(actual, expected);
+    }
+    #[test]
+    fn truncated_string_reports_unclosed_quotes() {
+        let expected = Err(TokenizeError::UnclosedQuotes(Position {
+            offset: 1,
+            line: 1,
+            column: 2,
+        }));
+        let actual = tokenize("\"");
+        assert_eq!(actual, expected);
+    }
 }